@@ -0,0 +1,36 @@
+use crate::vm::{maybe_relocatable::PyMaybeRelocatable, relocatable::PyRelocatable};
+use cairo_vm::{types::relocatable::MaybeRelocatable, vm::vm_core::VirtualMachine};
+use pyo3::prelude::*;
+
+/// Python wrapper around cairo-vm's `MemorySegmentManager`, borrowed for the lifetime of a
+/// single `CairoRunner.segments` access.
+#[pyclass(name = "MemorySegmentManager", unsendable)]
+pub struct PyMemorySegmentManager {
+    pub vm: *mut VirtualMachine,
+}
+
+impl PyMemorySegmentManager {
+    fn vm(&mut self) -> &mut VirtualMachine {
+        unsafe { &mut *self.vm }
+    }
+}
+
+#[pymethods]
+impl PyMemorySegmentManager {
+    /// Write `data` contiguously starting at `ptr`, returning the pointer just past it. Used to
+    /// inject fixture data after `initialize_segments` but before running.
+    fn load_data(
+        &mut self,
+        ptr: PyRelocatable,
+        data: Vec<PyMaybeRelocatable>,
+    ) -> PyResult<PyRelocatable> {
+        let data: Vec<MaybeRelocatable> = data.into_iter().map(MaybeRelocatable::from).collect();
+        let end = self
+            .vm()
+            .segments
+            .load_data(ptr.inner, &data)
+            .map_err(|e| PyErr::new::<pyo3::exceptions::PyRuntimeError, _>(e.to_string()))?;
+
+        Ok(PyRelocatable { inner: end })
+    }
+}
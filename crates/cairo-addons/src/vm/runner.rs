@@ -1,4 +1,11 @@
-use std::{cell::RefCell, collections::HashMap, rc::Rc};
+use std::{
+    cell::RefCell,
+    collections::HashMap,
+    fs::File,
+    io::{BufWriter, Write},
+    path::Path,
+    rc::Rc,
+};
 
 use crate::vm::{
     layout::PyLayout, maybe_relocatable::PyMaybeRelocatable, program::PyProgram,
@@ -14,15 +21,19 @@ use cairo_vm::{
     },
     vm::{
         errors::vm_exception::VmException,
-        runners::{builtin_runner::BuiltinRunner, cairo_runner::CairoRunner as RustCairoRunner},
+        runners::{
+            builtin_runner::BuiltinRunner, cairo_pie::CairoPie,
+            cairo_runner::CairoRunner as RustCairoRunner,
+        },
         security::verify_secure_runner,
     },
 };
 use num_traits::Zero;
 use polars::prelude::*;
 use pyo3::{
+    exceptions::PyException,
     prelude::*,
-    types::{IntoPyDict, PyDict},
+    types::{IntoPyDict, PyBytes, PyDict},
 };
 use pyo3_polars::PyDataFrame;
 use std::ffi::CString;
@@ -37,6 +48,20 @@ pub struct PyCairoRunner {
     allow_missing_builtins: bool,
     builtins: Vec<BuiltinName>,
     enable_pythonic_hints: bool,
+    proof_mode: bool,
+    /// Tasks queued by `load_program_task`, consumed by `run_bootloader`.
+    tasks: Vec<BootloaderTask>,
+    /// The shared output segment all bootloader tasks write into, once `run_bootloader` starts.
+    bootloader_output_segment: Option<Relocatable>,
+}
+
+/// A Cairo program loaded into its own segment by `load_program_task`, awaiting execution as
+/// part of a `run_bootloader` chain.
+struct BootloaderTask {
+    program_base: Relocatable,
+    program_len: usize,
+    args: Vec<MaybeRelocatable>,
+    builtins: Vec<BuiltinName>,
 }
 
 #[pymethods]
@@ -79,6 +104,9 @@ impl PyCairoRunner {
                 allow_missing_builtins,
                 builtins: program.inner.iter_builtins().copied().collect(),
                 enable_pythonic_hints,
+                proof_mode,
+                tasks: Vec::new(),
+                bootloader_output_segment: None,
             });
         }
 
@@ -137,6 +165,9 @@ except Exception as e:
             allow_missing_builtins,
             builtins: program.inner.iter_builtins().copied().collect(),
             enable_pythonic_hints,
+            proof_mode,
+            tasks: Vec::new(),
+            bootloader_output_segment: None,
         })
     }
 
@@ -151,14 +182,26 @@ except Exception as e:
     }
 
     /// Initialize the runner with the given stack and entrypoint offset.
-    #[pyo3(signature = (stack, entrypoint, ordered_builtins=None))]
+    ///
+    /// When `entrypoint_name` is given (the entrypoint's full name, e.g. `__main__.main`), its
+    /// declared implicit argument list is checked against `self.builtins` first, via
+    /// `check_main_signature` — after `ordered_builtins`, if given, has been applied to
+    /// `self.builtins` by `builtins_stack`, so the check always validates against the builtin
+    /// order actually used to build the stack.
+    #[pyo3(signature = (stack, entrypoint, ordered_builtins=None, entrypoint_name=None))]
     pub fn initialize_vm(
         &mut self,
         stack: Vec<PyMaybeRelocatable>,
         entrypoint: usize,
         ordered_builtins: Option<Vec<String>>,
+        entrypoint_name: Option<String>,
     ) -> PyResult<PyRelocatable> {
         let initial_stack = self.builtins_stack(ordered_builtins)?;
+
+        if let Some(entrypoint_name) = &entrypoint_name {
+            self.check_main_signature(entrypoint_name)?;
+        }
+
         let stack = initial_stack.into_iter().chain(stack.into_iter().map(|x| x.into())).collect();
 
         let return_fp = self.inner.vm.add_memory_segment();
@@ -193,6 +236,22 @@ except Exception as e:
         })
     }
 
+    /// The segment index and used size of every builtin's segment, for post-run auditing over
+    /// `segment_usage` (e.g. a Python-side secure-run check).
+    fn get_builtin_segments_info(&self) -> PyResult<HashMap<String, (usize, usize)>> {
+        self.inner
+            .vm
+            .builtin_runners
+            .iter()
+            .map(|builtin_runner| {
+                let size = builtin_runner
+                    .get_used_cells(&self.inner.vm.segments)
+                    .map_err(|e| PyErr::new::<pyo3::exceptions::PyRuntimeError, _>(e.to_string()))?;
+                Ok((builtin_runner.name().to_str().to_string(), (builtin_runner.base(), size)))
+            })
+            .collect()
+    }
+
     #[getter]
     fn ap(&self) -> PyRelocatable {
         PyRelocatable { inner: self.inner.vm.get_ap() }
@@ -237,7 +296,7 @@ except Exception as e:
         self.inner
             .run_until_pc(address.inner, &mut hint_processor)
             .map_err(|e| VmException::from_vm_error(&self.inner, e))
-            .map_err(|e| PyErr::new::<pyo3::exceptions::PyRuntimeError, _>(e.to_string()))?;
+            .map_err(|e| Python::with_gil(|py| self.vm_exception_to_pyerr(py, e)))?;
 
         self.inner
             .end_run(false, false, &mut hint_processor)
@@ -262,7 +321,7 @@ except Exception as e:
 
     fn verify_secure_runner(&mut self) -> PyResult<()> {
         verify_secure_runner(&self.inner, true, None)
-            .map_err(|e| PyErr::new::<pyo3::exceptions::PyRuntimeError, _>(e.to_string()))?;
+            .map_err(|e| Python::with_gil(|py| self.structured_error(py, e.to_string())))?;
 
         Ok(())
     }
@@ -278,7 +337,7 @@ except Exception as e:
     fn relocate(&mut self) -> PyResult<()> {
         self.inner
             .relocate(true)
-            .map_err(|e| PyErr::new::<pyo3::exceptions::PyRuntimeError, _>(e.to_string()))?;
+            .map_err(|e| Python::with_gil(|py| self.structured_error(py, e.to_string())))?;
 
         Ok(())
     }
@@ -314,17 +373,454 @@ except Exception as e:
 
         Ok(PyDataFrame(df))
     }
+
+    /// Build the Cairo PIE (Position-Independent Execution) bundle for this run.
+    ///
+    /// The returned object bundles the program metadata (bytecode hash, per-builtin segment
+    /// sizes, execution-segment size, `ret_fp`/`ret_pc`), the memory by segment (kept
+    /// segment-relative, not flattened via `relocate()` — that's what makes it position-
+    /// independent), the execution resources, and each builtin's additional data, so it can be
+    /// handed to a downstream bootloader or a SHARP-style prover.
+    fn get_cairo_pie(&self) -> PyResult<PyCairoPie> {
+        let pie = self
+            .inner
+            .get_cairo_pie()
+            .map_err(|e| PyErr::new::<pyo3::exceptions::PyRuntimeError, _>(e.to_string()))?;
+
+        Ok(PyCairoPie { inner: pie })
+    }
+
+    /// Write the Cairo PIE for this run to a zip archive at `path`.
+    fn write_cairo_pie_zip(&self, path: String) -> PyResult<()> {
+        let pie = self
+            .inner
+            .get_cairo_pie()
+            .map_err(|e| PyErr::new::<pyo3::exceptions::PyRuntimeError, _>(e.to_string()))?;
+
+        pie.write_zip_file(Path::new(&path))
+            .map_err(|e| PyErr::new::<pyo3::exceptions::PyRuntimeError, _>(e.to_string()))?;
+
+        Ok(())
+    }
+
+    /// Write the relocated trace as cairo-vm's canonical binary trace file: each entry as three
+    /// little-endian u64s, in `ap`, `fp`, `pc` order. Requires a `proof_mode` run.
+    fn write_binary_trace(&self, path: String) -> PyResult<()> {
+        self.ensure_proof_mode()?;
+
+        let relocated_trace = self.inner.relocated_trace.as_ref().ok_or_else(|| {
+            PyErr::new::<pyo3::exceptions::PyRuntimeError, _>(
+                "Trace not relocated; call relocate() first",
+            )
+        })?;
+
+        let mut file = BufWriter::new(
+            File::create(&path)
+                .map_err(|e| PyErr::new::<pyo3::exceptions::PyIOError, _>(e.to_string()))?,
+        );
+        for entry in relocated_trace.iter() {
+            file.write_all(&encode_trace_entry(entry.ap, entry.fp, entry.pc))
+                .map_err(|e| PyErr::new::<pyo3::exceptions::PyIOError, _>(e.to_string()))?;
+        }
+
+        Ok(())
+    }
+
+    /// Write the relocated memory as cairo-vm's canonical binary memory file: each cell as an
+    /// 8-byte little-endian address followed by its 32-byte little-endian field element value,
+    /// in address order. Requires a `proof_mode` run.
+    fn write_binary_memory(&self, path: String) -> PyResult<()> {
+        self.ensure_proof_mode()?;
+
+        if self.inner.relocated_trace.is_none() {
+            return Err(PyErr::new::<pyo3::exceptions::PyRuntimeError, _>(
+                "Memory not relocated; call relocate() first",
+            ));
+        }
+
+        let mut file = BufWriter::new(
+            File::create(&path)
+                .map_err(|e| PyErr::new::<pyo3::exceptions::PyIOError, _>(e.to_string()))?,
+        );
+        for (address, value) in self.inner.relocated_memory.iter().enumerate() {
+            let Some(value) = value else {
+                continue;
+            };
+            file.write_all(&encode_memory_cell(address, value))
+                .map_err(|e| PyErr::new::<pyo3::exceptions::PyIOError, _>(e.to_string()))?;
+        }
+
+        Ok(())
+    }
+
+    /// Write the AIR public input (program segment, rc bounds, per-builtin memory segment
+    /// addresses, public memory) to `path`, as consumed by a STARK prover. Requires a
+    /// `proof_mode` run.
+    fn write_air_public_input(&self, path: String) -> PyResult<()> {
+        self.ensure_proof_mode()?;
+
+        let public_input = self
+            .inner
+            .get_air_public_input()
+            .map_err(|e| PyErr::new::<pyo3::exceptions::PyRuntimeError, _>(e.to_string()))?;
+        let json = public_input
+            .serialize_json()
+            .map_err(|e| PyErr::new::<pyo3::exceptions::PyRuntimeError, _>(e.to_string()))?;
+
+        std::fs::write(&path, json)
+            .map_err(|e| PyErr::new::<pyo3::exceptions::PyIOError, _>(e.to_string()))?;
+
+        Ok(())
+    }
+
+    /// Write the AIR private input (trace/memory file references plus per-builtin private data)
+    /// to `path`. `trace_path`/`memory_path` must point at files already written via
+    /// `write_binary_trace`/`write_binary_memory`. Requires a `proof_mode` run.
+    fn write_air_private_input(
+        &self,
+        trace_path: String,
+        memory_path: String,
+        path: String,
+    ) -> PyResult<()> {
+        self.ensure_proof_mode()?;
+
+        if self.inner.relocated_trace.is_none() {
+            return Err(PyErr::new::<pyo3::exceptions::PyRuntimeError, _>(
+                "AIR private input requires a relocated run; call relocate() first",
+            ));
+        }
+
+        let private_input = self.inner.get_air_private_input();
+        private_input
+            .to_file(Path::new(&path), Path::new(&trace_path), Path::new(&memory_path))
+            .map_err(|e| PyErr::new::<pyo3::exceptions::PyRuntimeError, _>(e.to_string()))?;
+
+        Ok(())
+    }
+
+    /// Load a compiled program as a bootloader task: allocate a fresh segment, write the
+    /// program's bytecode into it, and record its declared builtins and call args for
+    /// `run_bootloader` to execute later.
+    fn load_program_task(
+        &mut self,
+        program: &PyProgram,
+        args: Vec<PyMaybeRelocatable>,
+    ) -> PyResult<()> {
+        let data: Vec<MaybeRelocatable> = program.inner.iter_data().cloned().collect();
+        let program_len = data.len();
+
+        let program_base = self.inner.vm.add_memory_segment();
+        self.inner
+            .vm
+            .segments
+            .load_data(program_base, &data)
+            .map_err(|e| PyErr::new::<pyo3::exceptions::PyRuntimeError, _>(e.to_string()))?;
+
+        self.tasks.push(BootloaderTask {
+            program_base,
+            program_len,
+            args: args.into_iter().map(MaybeRelocatable::from).collect(),
+            builtins: program.inner.iter_builtins().copied().collect(),
+        });
+
+        Ok(())
+    }
+
+    /// Run every task queued by `load_program_task`, in order, under this runner's VM: for each
+    /// task, select the subset of the VM's builtin pointers it declared (preserving the global
+    /// builtin order), build its entry stack, run it to its own return pc, and read back its
+    /// final builtin pointers before chaining into the next task. All tasks share one output
+    /// segment, so their outputs compose into a single aggregate output (see
+    /// `bootloader_output_segment`).
+    fn run_bootloader(&mut self, resources: PyRunResources) -> PyResult<()> {
+        let output_segment = self.inner.vm.add_memory_segment();
+        self.bootloader_output_segment = Some(output_segment);
+        let mut output_ptr = MaybeRelocatable::from(output_segment);
+
+        // `RunContext::get_ap()`/`get_fp()` (and `self.execution_base()` above) hard-code the
+        // execution segment to the one right after the program segment. Every task's frame must
+        // land in that same fixed segment, appended after the previous task's frame, not in a
+        // fresh segment of its own.
+        let mut execution_ptr = self.inner.program_base.map(|x| Relocatable {
+            segment_index: x.segment_index + 1,
+            offset: 0,
+        })
+        .ok_or_else(|| {
+            PyErr::new::<pyo3::exceptions::PyRuntimeError, _>(
+                "run_bootloader requires initialize_segments() to have been called first",
+            )
+        })?;
+
+        let tasks = std::mem::take(&mut self.tasks);
+        for task in tasks.iter() {
+            let task_builtins = select_task_builtins(&self.builtins, &task.builtins);
+            let mut stack = self.builtin_stack_for(&task_builtins)?;
+            stack.extend(task.args.clone());
+            stack.push(output_ptr.clone());
+
+            let return_fp = self.inner.vm.add_memory_segment();
+            let end = (task.program_base + task.program_len)
+                .map_err(|e| PyErr::new::<pyo3::exceptions::PyRuntimeError, _>(e.to_string()))?;
+            stack.push(return_fp.into());
+            stack.push(end.into());
+
+            let ap = self
+                .inner
+                .vm
+                .segments
+                .load_data(execution_ptr, &stack)
+                .map_err(|e| PyErr::new::<pyo3::exceptions::PyRuntimeError, _>(e.to_string()))?;
+
+            self.inner.vm.run_context.pc = task.program_base;
+            self.inner.vm.run_context.ap = ap.offset;
+            self.inner.vm.run_context.fp = ap.offset;
+
+            let mut hint_processor = if self.enable_pythonic_hints {
+                HintProcessor::default()
+                    .with_run_resources(resources.inner.clone())
+                    .with_dynamic_python_hints()
+                    .build()
+            } else {
+                HintProcessor::default().with_run_resources(resources.inner.clone()).build()
+            };
+
+            self.inner
+                .run_until_pc(end, &mut hint_processor)
+                .map_err(|e| VmException::from_vm_error(&self.inner, e))
+                .map_err(|e| Python::with_gil(|py| self.vm_exception_to_pyerr(py, e)))?;
+            execution_ptr = self.inner.vm.get_ap();
+
+            let mut pointer = self.inner.vm.get_ap();
+            let mut output_for_task = output_ptr.clone();
+            for builtin_name in task_builtins.iter().rev() {
+                if let Some(builtin_runner) =
+                    self.inner.vm.builtin_runners.iter_mut().find(|b| b.name() == *builtin_name)
+                {
+                    let new_pointer =
+                        builtin_runner.final_stack(&self.inner.vm.segments, pointer).map_err(
+                            |e| PyErr::new::<pyo3::exceptions::PyRuntimeError, _>(e.to_string()),
+                        )?;
+                    if *builtin_name == BuiltinName::output {
+                        output_for_task = self
+                            .inner
+                            .vm
+                            .get_maybe(&(pointer - 1).unwrap())
+                            .unwrap_or_else(|| output_for_task.clone());
+                    }
+                    pointer = new_pointer;
+                }
+            }
+            output_ptr = output_for_task;
+        }
+
+        Ok(())
+    }
+
+    /// The segment all bootloader tasks wrote their output into, so the composed output of every
+    /// task can be read after `run_bootloader` completes.
+    #[getter]
+    fn bootloader_output_segment(&self) -> Option<PyRelocatable> {
+        self.bootloader_output_segment.map(|inner| PyRelocatable { inner })
+    }
+
+    /// Cross-check `entrypoint`'s declared implicit argument list (`<entrypoint>.ImplicitArgs`,
+    /// from the program identifiers) against `self.builtins`, the same way the Cairo compiler
+    /// enforces that a function's implicit builtin arguments appear, named and ordered, as
+    /// `{builtin}_ptr`.
+    /// Fails with a precise error naming the expected ordered argument list, so a builtin/arg
+    /// mismatch is caught here instead of surfacing as an opaque memory error deep in the VM.
+    fn check_main_signature(&self, entrypoint: &str) -> PyResult<()> {
+        let args_scope = format!("{entrypoint}.ImplicitArgs");
+        let args_identifier = self
+            .inner
+            .program
+            .iter_identifiers()
+            .find(|(name, _)| *name == args_scope)
+            .map(|(_, identifier)| identifier)
+            .ok_or_else(|| {
+                PyErr::new::<pyo3::exceptions::PyValueError, _>(format!(
+                    "No argument list found for entrypoint `{entrypoint}` (missing identifier `{args_scope}`)"
+                ))
+            })?;
+
+        let members = args_identifier.members.as_ref().ok_or_else(|| {
+            PyErr::new::<pyo3::exceptions::PyValueError, _>(format!(
+                "Entrypoint `{entrypoint}` argument list `{args_scope}` has no members"
+            ))
+        })?;
+
+        let mut actual_args: Vec<(usize, String)> =
+            members.iter().map(|(name, member)| (member.offset, name.clone())).collect();
+        actual_args.sort_by_key(|(offset, _)| *offset);
+        let actual_args: Vec<String> = actual_args.into_iter().map(|(_, name)| name).collect();
+
+        let expected_args: Vec<String> =
+            self.builtins.iter().map(|b| format!("{}_ptr", b.to_str())).collect();
+
+        if !leading_args_match(&actual_args, &expected_args) {
+            return Err(PyErr::new::<pyo3::exceptions::PyValueError, _>(format!(
+                "Entrypoint `{entrypoint}` expects builtin arguments {expected_args:?} (in that \
+                 order) as its leading implicit arguments, but its declared implicit argument list \
+                 is {actual_args:?}"
+            )));
+        }
+
+        Ok(())
+    }
+}
+
+/// Raised in place of a bare `PyRuntimeError` when the VM run fails in `run_until_pc` or
+/// `run_bootloader` (carrying the structured data `VmException` already computes via
+/// `from_vm_error`), and when `verify_secure_runner` or `relocate` fails (pc and message only),
+/// so Python callers can pattern-match on error kind instead of scraping a string. Failures of
+/// `end_run`, `verify_auto_deductions` and `read_return_values` are raised as `RuntimeError`.
+#[pyclass(extends = PyException, name = "CairoVmException")]
+pub struct PyCairoVmException {
+    #[pyo3(get)]
+    pc: PyRelocatable,
+    #[pyo3(get)]
+    relocated_pc: Option<usize>,
+    #[pyo3(get)]
+    message: String,
+    #[pyo3(get)]
+    cairo_traceback: Option<String>,
+    #[pyo3(get)]
+    source_location: Option<String>,
+}
+
+#[pymethods]
+impl PyCairoVmException {
+    #[new]
+    #[pyo3(signature = (message, pc, relocated_pc=None, cairo_traceback=None, source_location=None))]
+    fn new(
+        message: String,
+        pc: PyRelocatable,
+        relocated_pc: Option<usize>,
+        cairo_traceback: Option<String>,
+        source_location: Option<String>,
+    ) -> Self {
+        Self { pc, relocated_pc, message, cairo_traceback, source_location }
+    }
+
+    fn __str__(&self) -> String {
+        self.message.clone()
+    }
+}
+
+/// Python wrapper around cairo-vm's `CairoPie`, the PIE bundle `CairoRunner.get_cairo_pie`
+/// produces after a run.
+#[pyclass(name = "CairoPie", unsendable)]
+pub struct PyCairoPie {
+    inner: CairoPie,
+}
+
+#[pymethods]
+impl PyCairoPie {
+    /// Serialize the PIE (metadata, memory, execution resources, additional data) to JSON bytes,
+    /// so Python can parse it into a dict or persist it without going through the zip format.
+    fn to_bytes(&self, py: Python) -> PyResult<Py<PyBytes>> {
+        let bytes = serde_json::to_vec(&self.inner)
+            .map_err(|e| PyErr::new::<pyo3::exceptions::PyRuntimeError, _>(e.to_string()))?;
+
+        Ok(PyBytes::new(py, &bytes).into())
+    }
+
+    /// Write this PIE to a zip archive at `path`, in the format cairo-vm's own CLI produces.
+    fn write_zip(&self, path: String) -> PyResult<()> {
+        self.inner
+            .write_zip_file(Path::new(&path))
+            .map_err(|e| PyErr::new::<pyo3::exceptions::PyRuntimeError, _>(e.to_string()))?;
+
+        Ok(())
+    }
+}
+
+/// Encode a relocated trace entry as cairo-vm's canonical binary trace format: three
+/// little-endian u64s, in `ap`, `fp`, `pc` order.
+fn encode_trace_entry(ap: usize, fp: usize, pc: usize) -> [u8; 24] {
+    let mut bytes = [0u8; 24];
+    bytes[0..8].copy_from_slice(&(ap as u64).to_le_bytes());
+    bytes[8..16].copy_from_slice(&(fp as u64).to_le_bytes());
+    bytes[16..24].copy_from_slice(&(pc as u64).to_le_bytes());
+    bytes
+}
+
+/// Encode a relocated memory cell as cairo-vm's canonical binary memory format: an 8-byte
+/// little-endian address followed by the value's 32-byte little-endian representation.
+fn encode_memory_cell(address: usize, value: &cairo_vm::Felt252) -> Vec<u8> {
+    let mut bytes = Vec::with_capacity(40);
+    bytes.extend_from_slice(&(address as u64).to_le_bytes());
+    bytes.extend_from_slice(&value.to_bytes_le());
+    bytes
+}
+
+/// The subset of `global_order` that `declared` contains, preserving `global_order`'s order.
+/// Used to pick each bootloader task's builtin pointers without disturbing the runner's own
+/// builtin order.
+fn select_task_builtins(global_order: &[BuiltinName], declared: &[BuiltinName]) -> Vec<BuiltinName> {
+    global_order.iter().filter(|b| declared.contains(b)).copied().collect()
+}
+
+/// Whether `actual`'s leading arguments are exactly `expected`, in order.
+fn leading_args_match(actual: &[String], expected: &[String]) -> bool {
+    actual.len() >= expected.len() && actual[..expected.len()] == expected[..]
 }
 
 impl PyCairoRunner {
+    /// Turn a `VmException` (already built via `VmException::from_vm_error`) into a
+    /// `CairoVmException`, pulling out the failing pc, the Cairo-level traceback, and the source
+    /// file/line from the program's debug info when available.
+    fn vm_exception_to_pyerr(&self, py: Python, e: VmException) -> PyErr {
+        let pc = PyRelocatable { inner: e.pc };
+        // `run_until_pc` and `run_bootloader` (the only callers of this helper) always fail
+        // before `relocate()` is ever called, so there is no relocation table yet to map `e.pc`
+        // through; any previous run's `relocated_trace` would be stale data unrelated to this
+        // failure. Leave it `None` rather than report something misleading.
+        let relocated_pc = None;
+        let source_location = e.inst_location.as_ref().map(|inst_location| {
+            format!(
+                "{}:{}:{}",
+                inst_location.location.input_file.filename,
+                inst_location.location.start_line,
+                inst_location.location.start_col
+            )
+        });
+        let message = e.inner_exc.to_string();
+        let cairo_traceback = e.traceback.clone();
+
+        match Py::new(
+            py,
+            PyCairoVmException::new(message, pc, relocated_pc, cairo_traceback, source_location),
+        ) {
+            Ok(obj) => PyErr::from_value(obj.into_bound(py).into_any()),
+            Err(err) => err,
+        }
+    }
+
+    /// Like `vm_exception_to_pyerr`, for failures (verify/relocate) that don't carry a
+    /// `VmException` of their own: reports the current vm pc with no traceback/source location.
+    fn structured_error(&self, py: Python, message: String) -> PyErr {
+        let pc = PyRelocatable { inner: self.inner.vm.get_pc() };
+        match Py::new(py, PyCairoVmException::new(message, pc, None, None, None)) {
+            Ok(obj) => PyErr::from_value(obj.into_bound(py).into_any()),
+            Err(err) => err,
+        }
+    }
+
+    fn ensure_proof_mode(&self) -> PyResult<()> {
+        if !self.proof_mode {
+            return Err(PyErr::new::<pyo3::exceptions::PyRuntimeError, _>(
+                "This operation requires the runner to have been created with proof_mode=true",
+            ));
+        }
+
+        Ok(())
+    }
+
     fn builtins_stack(
         &mut self,
         ordered_builtins: Option<Vec<String>>,
     ) -> PyResult<Vec<MaybeRelocatable>> {
-        let mut stack = Vec::new();
-        let builtin_runners =
-            self.inner.vm.builtin_runners.iter().map(|b| (b.name(), b)).collect::<HashMap<_, _>>();
-
         if let Some(names) = ordered_builtins {
             self.builtins = names
                 .iter()
@@ -338,7 +834,18 @@ impl PyCairoRunner {
                 })
                 .collect::<PyResult<Vec<_>>>()?;
         };
-        for builtin_name in self.builtins.iter() {
+        self.builtin_stack_for(&self.builtins.clone())
+    }
+
+    /// Like `builtins_stack`, but takes the builtin order explicitly instead of reading/writing
+    /// `self.builtins` — for callers (e.g. `run_bootloader`) that need a stack for a builtin
+    /// subset without clobbering the runner's own builtin order.
+    fn builtin_stack_for(&self, builtins: &[BuiltinName]) -> PyResult<Vec<MaybeRelocatable>> {
+        let mut stack = Vec::new();
+        let builtin_runners =
+            self.inner.vm.builtin_runners.iter().map(|b| (b.name(), b)).collect::<HashMap<_, _>>();
+
+        for builtin_name in builtins {
             if let Some(builtin_runner) = builtin_runners.get(builtin_name) {
                 stack.append(&mut builtin_runner.initial_stack());
             } else {
@@ -390,3 +897,65 @@ impl PyCairoRunner {
         Ok(())
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn encode_trace_entry_orders_ap_fp_pc_as_little_endian_u64s() {
+        let bytes = encode_trace_entry(1, 2, 3);
+        assert_eq!(&bytes[0..8], &1u64.to_le_bytes());
+        assert_eq!(&bytes[8..16], &2u64.to_le_bytes());
+        assert_eq!(&bytes[16..24], &3u64.to_le_bytes());
+    }
+
+    #[test]
+    fn encode_memory_cell_prefixes_value_with_le_address() {
+        let value = cairo_vm::Felt252::from(42);
+        let bytes = encode_memory_cell(7, &value);
+        assert_eq!(bytes.len(), 40);
+        assert_eq!(&bytes[0..8], &7u64.to_le_bytes());
+        assert_eq!(&bytes[8..40], &value.to_bytes_le());
+    }
+
+    #[test]
+    fn select_task_builtins_preserves_global_order_and_ignores_undeclared() {
+        let global_order =
+            vec![BuiltinName::output, BuiltinName::pedersen, BuiltinName::range_check];
+        let declared = vec![BuiltinName::range_check, BuiltinName::output];
+
+        let selected = select_task_builtins(&global_order, &declared);
+
+        assert_eq!(selected, vec![BuiltinName::output, BuiltinName::range_check]);
+    }
+
+    #[test]
+    fn select_task_builtins_does_not_mutate_global_order() {
+        let global_order =
+            vec![BuiltinName::output, BuiltinName::pedersen, BuiltinName::range_check];
+        let before = global_order.clone();
+
+        let _ = select_task_builtins(&global_order, &[BuiltinName::pedersen]);
+
+        assert_eq!(global_order, before);
+    }
+
+    #[test]
+    fn leading_args_match_accepts_matching_prefix() {
+        let actual = vec!["output_ptr".to_string(), "pedersen_ptr".to_string(), "x".to_string()];
+        let expected = vec!["output_ptr".to_string(), "pedersen_ptr".to_string()];
+
+        assert!(leading_args_match(&actual, &expected));
+    }
+
+    #[test]
+    fn leading_args_match_rejects_wrong_order_or_too_few_args() {
+        let actual = vec!["pedersen_ptr".to_string(), "output_ptr".to_string()];
+        let expected = vec!["output_ptr".to_string(), "pedersen_ptr".to_string()];
+        assert!(!leading_args_match(&actual, &expected));
+
+        let too_few = vec!["output_ptr".to_string()];
+        assert!(!leading_args_match(&too_few, &expected));
+    }
+}